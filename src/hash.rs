@@ -16,7 +16,7 @@ use digest::Digest;
 /// # Example
 /// ```rust
 /// use seedselection::hash::compute_hash;
-/// use sha2::Sha256;
+/// use sha2::{Digest, Sha256};
 /// let hash = compute_hash("ctx", b"seed", 1, Sha256::new);
 /// ```
 pub fn compute_hash<D, F>(name: &str, seed: &[u8], seq: u64, mut hasher: F) -> Vec<u8>
@@ -32,6 +32,48 @@ where
     h.finalize().to_vec()
 }
 
+/// A hasher pre-loaded with the `name ++ seed` prefix used by [`compute_hash`], so that the
+/// fixed prefix work can be amortized across many `seq` values (e.g. epoch rotation).
+///
+/// # Example
+/// ```rust
+/// use seedselection::hash::HashPrefix;
+/// use sha2::{Digest, Sha256};
+///
+/// let prefix = HashPrefix::new("ctx", b"seed", Sha256::new);
+/// let hash_for_seq_1 = prefix.hash_seq(1);
+/// ```
+pub struct HashPrefix<D: Digest + Clone> {
+    engine: D,
+}
+
+impl<D: Digest + Clone> HashPrefix<D> {
+    /// Builds a `HashPrefix` by absorbing `name` and `seed` into a fresh hasher once.
+    ///
+    /// # Arguments
+    /// - `name`: Context string
+    /// - `seed`: Random seed
+    /// - `hasher`: Closure returning a new hasher implementing [`digest::Digest`]
+    pub fn new(name: &str, seed: &[u8], mut hasher: impl FnMut() -> D) -> Self {
+        let mut engine = hasher();
+        engine.update(name.as_bytes());
+        engine.update(seed);
+
+        HashPrefix { engine }
+    }
+
+    /// Finishes the hash for a given `seq`, reusing the `name ++ seed` prefix.
+    ///
+    /// The result is byte-identical to `compute_hash(name, seed, seq, hasher)` for the
+    /// `name`/`seed`/`hasher` this `HashPrefix` was built from.
+    pub fn hash_seq(&self, seq: u64) -> Vec<u8> {
+        let mut h = self.engine.clone();
+        h.update(seq.to_string().as_bytes());
+
+        h.finalize().to_vec()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,4 +118,18 @@ mod tests {
 
         assert_eq!(result_hex, expected_hex, "Hash mismatch for empty seed");
     }
+
+    #[test]
+    fn test_hash_prefix_matches_compute_hash() {
+        let name = "test";
+        let seed = b"seed";
+
+        let prefix = HashPrefix::new(name, seed, Sha256::new);
+
+        for seq in [1u64, 2, 42] {
+            let expected = compute_hash(name, seed, seq, Sha256::new);
+            let actual = prefix.hash_seq(seq);
+            assert_eq!(actual, expected, "HashPrefix mismatch for seq {seq}");
+        }
+    }
 }
@@ -10,6 +10,10 @@
 //! - Pluggable hash algorithm
 //! - Selection based on XOR distance
 //! - Efficient selection based on XOR distance, using a max-heap to choose the closest items
+//! - Batched selection across multiple sequence numbers, reusing a prefixed hash state
+//! - Selection based on a swap-or-not oblivious shuffle, for proximity-unbiased permutations
+//! - Network size estimation and Sybil-clustering detection from a selected set
+//! - Bucket-spread selection, distributing picks across XOR-distance shells
 //!
 //! ## Security
 //! The selection process is designed to be secure against manipulation by malicious nodes, as it relies on a (pluggable) hash function and XOR distance which are difficult to predict or influence.
@@ -38,4 +42,6 @@
 //! ```
 
 pub mod hash;
+pub mod network_size;
+pub mod shuffle;
 pub mod xor_dist;
@@ -0,0 +1,192 @@
+//! This module implements network size estimation and Sybil-clustering detection from a
+//! selected set of ids.
+//!
+//! In a uniformly distributed 256-bit id space of size `N`, the expected XOR distance from a
+//! target hash to the `i`-th closest id is roughly `i * 2^256 / N`. Inverting this relation
+//! lets us estimate `N` from the observed closest distances, and comparing observed distances
+//! against the distances expected for a given `N` lets us flag ids that are suspiciously
+//! crowded around the target (a sign of Sybil clustering).
+
+use num_bigint::BigUint;
+
+/// Size, in bits, of the id space (ids are expected to be 32-byte SHA256 hashes).
+const ID_SPACE_BITS: u32 = 256;
+
+/// Default threshold below which the mean ratio of observed to expected distance is
+/// considered suspicious clustering.
+const DEFAULT_CLUSTERING_THRESHOLD: f64 = 0.5;
+
+// Computes the full-width XOR distance between a target hash and an id, matching the metric
+// used by `xor_dist::xor_distance_selection`.
+fn xor_distance(target_hash: &[u8], id: &[u8]) -> BigUint {
+    let target_value = BigUint::from_bytes_be(target_hash);
+    let id_value = BigUint::from_bytes_be(id);
+    target_value ^ id_value
+}
+
+// Converts a `BigUint` to an `f64` via its decimal representation. This loses precision for
+// very large values, which is acceptable since the result only feeds a statistical estimate.
+fn biguint_to_f64(value: &BigUint) -> f64 {
+    value.to_string().parse().unwrap_or(f64::INFINITY)
+}
+
+/// Estimates the total network size from the target hash and a set of selected closest ids.
+///
+/// For each `i`-th closest id (1-indexed, sorted by ascending XOR distance `d_i` to
+/// `target_hash`), the network size is estimated as `N_i ≈ i * 2^256 / d_i`, and the returned
+/// value is the average of `N_i` across all ids with a nonzero distance.
+///
+/// # Arguments
+/// * `target_hash` - The target hash ids were selected against (e.g. from [`crate::hash::compute_hash`]).
+/// * `ids` - The selected closest ids (e.g. from [`crate::xor_dist::xor_distance_selection`]).
+///
+/// # Returns
+/// The estimated network size, or `0.0` if `ids` is empty or every id exactly matches
+/// `target_hash`.
+pub fn estimate_network_size(target_hash: &[u8], ids: &[Vec<u8>]) -> f64 {
+    if ids.is_empty() {
+        return 0.0;
+    }
+
+    let space_size = BigUint::from(1u32) << ID_SPACE_BITS;
+
+    let mut distances: Vec<BigUint> = ids.iter().map(|id| xor_distance(target_hash, id)).collect();
+    distances.sort();
+
+    let estimates: Vec<f64> = distances
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| **d != BigUint::from(0u32))
+        .map(|(idx, d)| {
+            let i = idx + 1;
+            biguint_to_f64(&(&space_size * i as u32)) / biguint_to_f64(d)
+        })
+        .collect();
+
+    if estimates.is_empty() {
+        return 0.0;
+    }
+
+    estimates.iter().sum::<f64>() / estimates.len() as f64
+}
+
+/// Detects suspicious clustering of `ids` around `target_hash`, given an `expected_n` network
+/// size (e.g. from [`estimate_network_size`] or an out-of-band estimate).
+///
+/// Clustering is suspected when the observed closest distances are, on average, much smaller
+/// than what is expected for a uniformly distributed network of size `expected_n` — i.e. the
+/// mean ratio of observed to expected distance falls below `threshold` (defaults to `0.5`
+/// when `None`).
+///
+/// # Arguments
+/// * `target_hash` - The target hash ids were selected against.
+/// * `ids` - The selected closest ids.
+/// * `expected_n` - The expected (or independently estimated) network size.
+/// * `threshold` - Mean observed/expected distance ratio below which clustering is flagged.
+///
+/// # Returns
+/// `true` if clustering is suspected, `false` otherwise (including when `ids` is empty or
+/// `expected_n` is not positive).
+pub fn detect_clustering(
+    target_hash: &[u8],
+    ids: &[Vec<u8>],
+    expected_n: f64,
+    threshold: Option<f64>,
+) -> bool {
+    if ids.is_empty() || expected_n <= 0.0 {
+        return false;
+    }
+
+    let threshold = threshold.unwrap_or(DEFAULT_CLUSTERING_THRESHOLD);
+    let space_size = BigUint::from(1u32) << ID_SPACE_BITS;
+
+    let mut distances: Vec<BigUint> = ids.iter().map(|id| xor_distance(target_hash, id)).collect();
+    distances.sort();
+
+    let ratios: Vec<f64> = distances
+        .iter()
+        .enumerate()
+        .map(|(idx, d)| {
+            let i = (idx + 1) as f64;
+            let expected_distance = biguint_to_f64(&space_size) * i / expected_n;
+            biguint_to_f64(d) / expected_distance
+        })
+        .collect();
+
+    if ratios.is_empty() {
+        return false;
+    }
+
+    let mean_ratio = ratios.iter().sum::<f64>() / ratios.len() as f64;
+    mean_ratio < threshold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Target hash of all zero bytes, so XOR distance to an id equals the id's numeric value.
+    const ZERO_HASH: [u8; 32] = [0u8; 32];
+
+    fn biguint_to_be_bytes(value: BigUint) -> Vec<u8> {
+        let mut bytes = vec![0u8; 32];
+        let value_bytes = value.to_bytes_be();
+        bytes[32 - value_bytes.len()..].copy_from_slice(&value_bytes);
+        bytes
+    }
+
+    #[test]
+    fn test_estimate_network_size_for_uniformly_spaced_distances() {
+        let space_size = BigUint::from(1u32) << ID_SPACE_BITS;
+        let n = 100u32;
+        let ids: Vec<Vec<u8>> = (1..=5u32)
+            .map(|i| biguint_to_be_bytes(&space_size * i / n))
+            .collect();
+
+        let estimated = estimate_network_size(&ZERO_HASH, &ids);
+        assert!(
+            (estimated - n as f64).abs() < 1.0,
+            "expected estimate close to {n}, got {estimated}"
+        );
+    }
+
+    #[test]
+    fn test_estimate_network_size_empty_ids() {
+        assert_eq!(estimate_network_size(&ZERO_HASH, &[]), 0.0);
+    }
+
+    #[test]
+    fn test_detect_clustering_flags_tightly_packed_ids() {
+        let expected_n = 100_000.0;
+        let ids: Vec<Vec<u8>> = (196..=200u32)
+            .map(|bit| biguint_to_be_bytes(BigUint::from(1u32) << bit))
+            .collect();
+
+        assert!(
+            detect_clustering(&ZERO_HASH, &ids, expected_n, None),
+            "tightly clustered ids should be flagged"
+        );
+    }
+
+    #[test]
+    fn test_detect_clustering_allows_expected_spread() {
+        let expected_n = 100_000u32;
+        let space_size = BigUint::from(1u32) << ID_SPACE_BITS;
+        let ids: Vec<Vec<u8>> = (1..=5u32)
+            .map(|i| biguint_to_be_bytes(&space_size * i / expected_n))
+            .collect();
+
+        assert!(
+            !detect_clustering(&ZERO_HASH, &ids, expected_n as f64, None),
+            "ids matching the expected spread should not be flagged"
+        );
+    }
+
+    #[test]
+    fn test_detect_clustering_empty_ids_or_non_positive_expected_n() {
+        assert!(!detect_clustering(&ZERO_HASH, &[], 100.0, None));
+
+        let ids = vec![vec![1u8; 32]];
+        assert!(!detect_clustering(&ZERO_HASH, &ids, 0.0, None));
+    }
+}
@@ -0,0 +1,276 @@
+//! This module implements swap-or-not oblivious shuffle selection.
+//!
+//! Unlike [`crate::xor_dist::xor_distance_selection`], which favors ids that are numerically
+//! close to a target hash, this module produces a uniform pseudorandom permutation of the ids
+//! that is independent of their numeric value. This is useful for fair leader rotation or
+//! committee assignment, where proximity bias is undesirable.
+
+use digest::Digest;
+
+/// Default number of swap-or-not rounds, chosen for good mixing over typical id set sizes.
+const DEFAULT_ROUNDS: u32 = 90;
+
+// Hashes the concatenation of `parts` with a fresh hasher instance.
+fn hash_parts<D, F>(hasher: &mut F, parts: &[&[u8]]) -> Vec<u8>
+where
+    D: Digest,
+    F: FnMut() -> D,
+{
+    let mut h = hasher();
+    for part in parts {
+        h.update(part);
+    }
+    h.finalize().to_vec()
+}
+
+// Number of bytes of hash output needed to index one of 256 bit-positions a byte at a time.
+const MIN_SOURCE_BYTES: usize = 32;
+
+// Like `hash_parts`, but expands the output to at least `min_len` bytes for hashers whose
+// digest is shorter than that (e.g. SHA-1's 20 bytes), by hashing successive
+// counter-suffixed extensions of `parts` and concatenating the results.
+fn expand_hash<D, F>(hasher: &mut F, parts: &[&[u8]], min_len: usize) -> Vec<u8>
+where
+    D: Digest,
+    F: FnMut() -> D,
+{
+    let mut out = hash_parts(hasher, parts);
+    let mut counter: u8 = 0;
+    while out.len() < min_len {
+        let counter_byte = [counter];
+        let mut extended: Vec<&[u8]> = Vec::with_capacity(parts.len() + 1);
+        extended.extend_from_slice(parts);
+        extended.push(&counter_byte);
+        out.extend_from_slice(&hash_parts(hasher, &extended));
+        counter = counter.wrapping_add(1);
+    }
+    out
+}
+
+// Interprets the first (up to) 8 bytes of `bytes` as a little-endian u64.
+fn le_bytes_to_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    let len = bytes.len().min(8);
+    buf[..len].copy_from_slice(&bytes[..len]);
+    u64::from_le_bytes(buf)
+}
+
+// Computes the shuffled position of index `i` among `count` elements using the swap-or-not
+// shuffle, without materializing the whole permutation.
+fn swap_or_not_index<D, F>(mut i: u64, count: u64, seed: &[u8], rounds: u32, hasher: &mut F) -> u64
+where
+    D: Digest,
+    F: FnMut() -> D,
+{
+    for round in 0..rounds {
+        let round_bytes = round.to_le_bytes();
+        let pivot_hash = hash_parts(hasher, &[seed, &round_bytes]);
+        let pivot = le_bytes_to_u64(&pivot_hash[..8]) % count;
+        let flip = (pivot + count - i) % count;
+        let position = i.max(flip);
+        let source = expand_hash(
+            hasher,
+            &[seed, &round_bytes, &(position / 256).to_le_bytes()],
+            MIN_SOURCE_BYTES,
+        );
+        let byte = source[((position % 256) / 8) as usize];
+        let bit = (byte >> (position % 8)) & 1;
+        if bit == 1 {
+            i = flip;
+        }
+    }
+    i
+}
+
+/// shuffle_selection takes a name, (random) seed, and sequence number to derive a permutation
+/// seed, then applies the swap-or-not oblivious shuffle to produce a full pseudorandom
+/// permutation of `ids` and returns the first `n` of them.
+///
+/// Unlike [`crate::xor_dist::xor_distance_selection`], the resulting order is a uniform
+/// permutation independent of the numeric value of the ids, so it carries no proximity bias.
+///
+/// # Arguments
+/// * `name` - A string identifier for the selection context.
+/// * `seed` - A 32 byte random seed as a byte slice.
+/// * `seq` - A sequence number (round/epoch).
+/// * `n` - The number of ids to select.
+/// * `ids` - A vector of ids to select from.
+/// * `hasher`: Closure returning a new hasher implementing [`digest::Digest`]
+/// * `rounds` - Number of swap-or-not rounds to run; defaults to 90 when `None`.
+///
+/// # Returns
+/// `Some(Vec<Vec<u8>>)` of the first `n` shuffled IDs, or `None` if `ids` is empty.
+///
+/// # Security
+/// The permutation is only as unpredictable as the seed. Use a secure, random seed for
+/// cryptographic applications.
+///
+/// # Example
+/// ```rust
+/// use sha2::{Sha256, Digest};
+///
+/// let ids = vec![b"peer1".to_vec(), b"peer2".to_vec()];
+/// let selected = seedselection::shuffle::shuffle_selection("ctx", b"seed", 0, 1, &ids, Sha256::new, None).unwrap();
+/// ```
+pub fn shuffle_selection<T, D>(
+    name: &str,
+    seed: &[u8],
+    seq: u64,
+    n: usize,
+    ids: &[T],
+    mut hasher: impl FnMut() -> D,
+    rounds: Option<u32>,
+) -> Option<Vec<Vec<u8>>>
+where
+    T: AsRef<[u8]>,
+    D: Digest,
+{
+    let count = ids.len();
+    if count == 0 {
+        return None;
+    }
+
+    let permutation_seed = crate::hash::compute_hash(name, seed, seq, &mut hasher);
+    let rounds = rounds.unwrap_or(DEFAULT_ROUNDS);
+
+    let mut permuted: Vec<Option<Vec<u8>>> = vec![None; count];
+    for (i, id) in ids.iter().enumerate() {
+        let new_pos = swap_or_not_index(
+            i as u64,
+            count as u64,
+            &permutation_seed,
+            rounds,
+            &mut hasher,
+        );
+        permuted[new_pos as usize] = Some(id.as_ref().to_vec());
+    }
+
+    Some(permuted.into_iter().flatten().take(n).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    static PEER_IDS: [&str; 5] = ["peer1", "peer2", "peer3", "peer4", "peer5"];
+
+    fn peerset() -> Vec<&'static [u8]> {
+        PEER_IDS.iter().map(|s| s.as_bytes()).collect()
+    }
+
+    #[test]
+    fn test_full_permutation_is_deterministic_and_a_bijection() {
+        let ids = peerset();
+
+        let a =
+            shuffle_selection("test", b"test-seed", 1, ids.len(), &ids, Sha256::new, None).unwrap();
+        let b =
+            shuffle_selection("test", b"test-seed", 1, ids.len(), &ids, Sha256::new, None).unwrap();
+        assert_eq!(a, b, "shuffle must be deterministic for the same inputs");
+
+        let mut sorted_a: Vec<Vec<u8>> = a.clone();
+        sorted_a.sort();
+        let mut sorted_ids: Vec<Vec<u8>> = ids.iter().map(|id| id.to_vec()).collect();
+        sorted_ids.sort();
+        assert_eq!(
+            sorted_a, sorted_ids,
+            "shuffle must be a permutation of the input ids"
+        );
+
+        let expected = vec![
+            PEER_IDS[2].as_bytes().to_vec(),
+            PEER_IDS[3].as_bytes().to_vec(),
+            PEER_IDS[1].as_bytes().to_vec(),
+            PEER_IDS[4].as_bytes().to_vec(),
+            PEER_IDS[0].as_bytes().to_vec(),
+        ];
+        assert_eq!(a, expected, "Shuffle mismatch");
+    }
+
+    #[test]
+    fn test_first_n_matches_prefix_of_full_permutation() {
+        let ids = peerset();
+
+        let full =
+            shuffle_selection("test", b"test-seed", 1, ids.len(), &ids, Sha256::new, None).unwrap();
+        let first_three =
+            shuffle_selection("test", b"test-seed", 1, 3, &ids, Sha256::new, None).unwrap();
+
+        assert_eq!(first_three, full[..3]);
+    }
+
+    #[test]
+    fn test_n_greater_than_ids_returns_full_permutation() {
+        let ids = peerset();
+
+        let actual =
+            shuffle_selection("test", b"test-seed", 1, 10, &ids, Sha256::new, None).unwrap();
+        assert_eq!(actual.len(), ids.len());
+    }
+
+    #[test]
+    fn test_different_seq_changes_permutation() {
+        let ids = peerset();
+
+        let seq1 =
+            shuffle_selection("test", b"test-seed", 1, ids.len(), &ids, Sha256::new, None).unwrap();
+        let seq2 =
+            shuffle_selection("test", b"test-seed", 2, ids.len(), &ids, Sha256::new, None).unwrap();
+
+        assert_ne!(
+            seq1, seq2,
+            "different sequence numbers should yield different permutations"
+        );
+    }
+
+    #[test]
+    fn test_rounds_beyond_255_do_not_reuse_earlier_round_material() {
+        // `rounds` is a public, unbounded u32; round 44 and round 300 must not reuse the same
+        // pivot/source hash material, or mixing quietly degrades for large round counts.
+        // If the round number were truncated to a byte, rounds 256..300 would replay rounds
+        // 0..44, so 300 rounds would equal 256 rounds followed by another 44 rounds.
+        let count = peerset().len() as u64;
+        let seed = crate::hash::compute_hash("test", b"test-seed", 1, Sha256::new);
+        let mut hasher = Sha256::new;
+
+        let rounds_300: Vec<u64> = (0..count)
+            .map(|i| swap_or_not_index(i, count, &seed, 300, &mut hasher))
+            .collect();
+        let rounds_256_then_44: Vec<u64> = (0..count)
+            .map(|i| {
+                let after_256 = swap_or_not_index(i, count, &seed, 256, &mut hasher);
+                swap_or_not_index(after_256, count, &seed, 44, &mut hasher)
+            })
+            .collect();
+
+        assert_ne!(
+            rounds_300, rounds_256_then_44,
+            "rounds 256..300 must not replay rounds 0..44 via truncation to a byte"
+        );
+    }
+
+    #[test]
+    fn test_shuffle_with_short_digest_hasher_does_not_panic() {
+        // SHA-1 has a 20-byte digest, shorter than the 32 bytes `swap_or_not_index` needs to
+        // index a bit position; this set is large enough to exercise `position` values past
+        // byte 20 and must not panic.
+        use sha1::Sha1;
+
+        let ids: Vec<Vec<u8>> = (0..200u32)
+            .map(|i| format!("peer{i}").into_bytes())
+            .collect();
+
+        let actual =
+            shuffle_selection("test", b"test-seed", 0, ids.len(), &ids, Sha1::new, None).unwrap();
+        assert_eq!(actual.len(), ids.len());
+    }
+
+    #[test]
+    fn test_no_ids() {
+        let ids: Vec<&[u8]> = Vec::new();
+
+        let actual = shuffle_selection("test", b"test-seed", 1, 3, &ids, Sha256::new, None);
+        assert_eq!(actual, None, "Selection mismatch for no ids");
+    }
+}
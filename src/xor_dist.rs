@@ -3,38 +3,54 @@
 use digest::Digest;
 use num_bigint::BigUint;
 use std::cmp::Ordering;
-use std::collections::BinaryHeap;
+use std::collections::{BTreeMap, BinaryHeap, VecDeque};
 
 // HeapEntry is an internal struct that holds an id and its xor distance from a hash value.
 //
+// `distance` is a fixed-width, big-endian byte array covering the full XOR distance
+// (not just its low bits), so ordering is the same as comparing the underlying
+// unsigned integers most-significant-byte first.
+//
 // PartialOrd and Ord are crucial for BinaryHeap to know how to order elements.
 // Since BinaryHeap is a max-heap, we'll implement these to order by 'distance' in reverse.
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct HeapEntry {
     id: Vec<u8>,
-    distance: u64,
+    distance: Vec<u8>,
 }
 
 // Implement Ord for HeapEntry to make it compatible with BinaryHeap.
 // BinaryHeap is a max-heap, so `cmp` should define what makes an element "greater".
-// We want the *smallest* distances to be considered "greater" in this context
-// so that BinaryHeap keeps the N smallest at the top (effectively acting as a min-heap
-// for our conceptual "smallest distance" goal).
-// This is done by reversing the comparison of distance.
+// We order directly by distance, so the *farthest* of the currently-kept entries is
+// "greatest" and sits at the top of the heap. That's what lets the selection loop evict
+// the farthest entry when a closer one arrives, keeping the heap holding the n closest ids.
+// For `Vec<u8>` of equal length this is already a most-significant-byte-first lexicographic
+// comparison.
 impl Ord for HeapEntry {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.distance.cmp(&other.distance).reverse()
+        self.distance.cmp(&other.distance)
     }
 }
 
 /// Implement PartialOrd for HeapEntry to allow comparisons between entries based on their distance.
-/// It does not need to reverse the comparison since Ord already does that.
+/// Delegates to `Ord::cmp` so the two impls can never disagree.
 impl PartialOrd for HeapEntry {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.distance.cmp(&other.distance))
+        Some(self.cmp(other))
     }
 }
 
+// Left-pads `bytes` with zeroes up to `width`, so that equal-length byte arrays can be
+// compared lexicographically as if they were big-endian integers of the same width.
+fn pad_be(bytes: &[u8], width: usize) -> Vec<u8> {
+    if bytes.len() >= width {
+        return bytes.to_vec();
+    }
+    let mut padded = vec![0u8; width - bytes.len()];
+    padded.extend_from_slice(bytes);
+    padded
+}
+
 /// xor_distance_selection takes a name, (random) seed, sequence number, number of ids to select (n),
 /// and a list of ids (hashes) to select from.
 ///
@@ -77,6 +93,66 @@ pub fn xor_distance_selection<T, D>(
 where
     T: AsRef<[u8]>,
     D: Digest,
+{
+    let hash_bytes = crate::hash::compute_hash(name, seed, seq, &mut hasher);
+    select_closest(&hash_bytes, n, ids, weights)
+}
+
+/// xor_distance_selection_batch runs [`xor_distance_selection`] across many `seqs` at once,
+/// reusing a [`crate::hash::HashPrefix`] so the `name ++ seed` prefix is only absorbed once
+/// regardless of how many sequence numbers are selected for (e.g. epoch rotation).
+///
+/// The selection for each `seq` is byte-identical to calling `xor_distance_selection` with
+/// that `seq` directly.
+///
+/// # Arguments
+/// * `prefix` - A [`crate::hash::HashPrefix`] built from the selection's `name` and `seed`.
+/// * `seqs` - The sequence numbers to select for.
+/// * `n` - The number of ids to select per sequence number.
+/// * `ids` - A vector of hashed ids to select from.
+/// * `weights` - Optional per-id weights, aligned with `ids`.
+///
+/// # Returns
+/// One `Vec<Vec<u8>>` of selected ids per entry in `seqs`, in the same order. An entry is
+/// empty wherever `ids` is empty.
+///
+/// # Example
+/// ```rust
+/// use sha2::{Sha256, Digest};
+/// use seedselection::hash::HashPrefix;
+/// use seedselection::xor_dist::xor_distance_selection_batch;
+///
+/// let ids = vec![b"peer1".to_vec(), b"peer2".to_vec()];
+/// let prefix = HashPrefix::new("ctx", b"seed", Sha256::new);
+/// let selected = xor_distance_selection_batch(&prefix, &[0, 1, 2], 1, &ids, None);
+/// assert_eq!(selected.len(), 3);
+/// ```
+pub fn xor_distance_selection_batch<T, D>(
+    prefix: &crate::hash::HashPrefix<D>,
+    seqs: &[u64],
+    n: usize,
+    ids: &[T],
+    weights: Option<&[u64]>,
+) -> Vec<Vec<Vec<u8>>>
+where
+    T: AsRef<[u8]>,
+    D: Digest + Clone,
+{
+    seqs.iter()
+        .map(|&seq| select_closest(&prefix.hash_seq(seq), n, ids, weights).unwrap_or_default())
+        .collect()
+}
+
+// select_closest picks the `n` ids closest to `hash_bytes` by full-width XOR distance, shared
+// by both the single-`seq` and batched selection entry points.
+fn select_closest<T>(
+    hash_bytes: &[u8],
+    n: usize,
+    ids: &[T],
+    weights: Option<&[u64]>,
+) -> Option<Vec<Vec<u8>>>
+where
+    T: AsRef<[u8]>,
 {
     let p = ids.len();
 
@@ -86,8 +162,17 @@ where
     if n >= p {
         return Some(ids.iter().map(|id| id.as_ref().to_vec()).collect());
     }
-    let hash_bytes = crate::hash::compute_hash(name, seed, seq, &mut hasher);
-    let hash_value = BigUint::from_bytes_be(&hash_bytes);
+    let hash_value = BigUint::from_bytes_be(hash_bytes);
+
+    // All distances are compared as equal-length, big-endian byte arrays, so pad to the
+    // widest operand (the longest id, or the hash itself) to keep comparisons correct
+    // over the full distance rather than just its low bits.
+    let width = ids
+        .iter()
+        .map(|id| id.as_ref().len())
+        .max()
+        .unwrap_or(0)
+        .max(hash_bytes.len());
 
     // We use Rust's BinaryHeap is a max-heap to keep the 'n' smallest elements,
     // if the heap size exceeds 'n' we check the largest element against the new element.
@@ -99,20 +184,19 @@ where
         let id_bytes = id.as_ref();
 
         let id_value = BigUint::from_bytes_be(id_bytes);
-        let distance_val = &hash_value ^ &id_value;
+        let mut distance_val = &hash_value ^ &id_value;
 
-        let mut distance = distance_val.to_u64_digits().first().cloned().unwrap_or(0);
         if let Some(weights) = weights {
             // If weights are provided, we apply them to the distance.
             // This assumes that the weights are aligned with the ids.
             if let Some(&weight) = weights.get(i).filter(|&w| *w > 0) {
                 // Apply weight to distance
-                distance /= weight;
+                distance_val /= weight;
             }
         }
         let entry = HeapEntry {
             id: id_bytes.to_vec(),
-            distance,
+            distance: pad_be(&distance_val.to_bytes_be(), width),
         };
 
         if max_heap.len() < n {
@@ -139,6 +223,116 @@ where
     Some(selected)
 }
 
+// Position of the most-significant differing bit of `distance` within a `width_bits`-wide
+// distance space, i.e. the Kademlia-style bucket index: `0` means the ids differ at the
+// top bit (farthest), `width_bits - 1` means they differ only at the bottom bit (nearest).
+// An exact match (`distance` is zero) gets its own bucket above that range, `width_bits`,
+// so it can never collide with (and be queued behind) the farthest ids at bucket `0`.
+fn bucket_index(distance: &BigUint, width_bits: u32) -> u32 {
+    match distance.bits() as u32 {
+        0 => width_bits,
+        bits => width_bits - bits,
+    }
+}
+
+/// xor_distance_selection_spread takes a name, (random) seed, sequence number, number of ids
+/// to select (n), and a list of ids, and returns ids spread across XOR-distance buckets
+/// relative to `hash(name, seed, seq)` rather than the `n` strictly-closest ids.
+///
+/// Each id is bucketed by the position of the most-significant bit where it differs from the
+/// target hash, analogous to Kademlia k-buckets/prefixes. Buckets are then visited round-robin
+/// from nearest to farthest, taking the closest remaining id from each non-empty bucket, until
+/// `n` ids are chosen. This gives a topologically diverse sample (near, mid, and far ids)
+/// rather than a tight cluster, which is useful for gossip fan-out and redundancy.
+///
+/// # Arguments
+/// * `name` - A string identifier for the selection context.
+/// * `seed` - A 32 byte random seed as a byte slice.
+/// * `seq` - A sequence number (round/epoch).
+/// * `n` - The number of ids to select.
+/// * `ids` - A vector of hashed ids to select from.
+/// * `hasher`: Closure returning a new hasher implementing [`digest::Digest`]
+///
+/// # Returns
+/// `Some(Vec<Vec<u8>>)` of the spread-selected ids (fewer than `n` only when `ids` has fewer
+/// than `n` entries), or `None` if `ids` is empty.
+///
+/// # Example
+/// ```rust
+/// use sha2::{Sha256, Digest};
+///
+/// let ids = vec![b"peer1".to_vec(), b"peer2".to_vec(), b"peer3".to_vec()];
+/// let selected = seedselection::xor_dist::xor_distance_selection_spread("ctx", b"seed", 0, 2, &ids, Sha256::new).unwrap();
+/// ```
+pub fn xor_distance_selection_spread<T, D>(
+    name: &str,
+    seed: &[u8],
+    seq: u64,
+    n: usize,
+    ids: &[T],
+    mut hasher: impl FnMut() -> D,
+) -> Option<Vec<Vec<u8>>>
+where
+    T: AsRef<[u8]>,
+    D: Digest,
+{
+    if ids.is_empty() {
+        return None;
+    }
+
+    let hash_bytes = crate::hash::compute_hash(name, seed, seq, &mut hasher);
+    let hash_value = BigUint::from_bytes_be(&hash_bytes);
+
+    let width_bits = ids
+        .iter()
+        .map(|id| id.as_ref().len())
+        .max()
+        .unwrap_or(0)
+        .max(hash_bytes.len()) as u32
+        * 8;
+
+    let mut by_bucket: BTreeMap<u32, Vec<(BigUint, Vec<u8>)>> = BTreeMap::new();
+    for id in ids {
+        let id_bytes = id.as_ref();
+        let id_value = BigUint::from_bytes_be(id_bytes);
+        let distance = &hash_value ^ &id_value;
+        let bucket = bucket_index(&distance, width_bits);
+        by_bucket
+            .entry(bucket)
+            .or_default()
+            .push((distance, id_bytes.to_vec()));
+    }
+
+    // Nearest bucket (highest index) first; within a bucket, closest id first.
+    let mut queues: Vec<VecDeque<Vec<u8>>> = by_bucket
+        .into_iter()
+        .rev()
+        .map(|(_, mut entries)| {
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            entries.into_iter().map(|(_, id)| id).collect()
+        })
+        .collect();
+
+    let mut selected = Vec::with_capacity(n.min(ids.len()));
+    'rounds: while selected.len() < n {
+        let mut progressed = false;
+        for queue in queues.iter_mut() {
+            if selected.len() >= n {
+                break 'rounds;
+            }
+            if let Some(id) = queue.pop_front() {
+                selected.push(id);
+                progressed = true;
+            }
+        }
+        if !progressed {
+            break;
+        }
+    }
+
+    Some(selected)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -289,8 +483,8 @@ mod tests {
             .collect();
 
         let expected = vec![
-            PEER_IDS_32BYTE_LITERAL_CASE[2].as_bytes(), // e42bbf85... (Placeholder)
-            PEER_IDS_32BYTE_LITERAL_CASE[3].as_bytes(), // a7a0243e... (Placeholder)
+            PEER_IDS_32BYTE_LITERAL_CASE[4].as_bytes(), // b2c3d4e5...
+            PEER_IDS_32BYTE_LITERAL_CASE[1].as_bytes(), // 3b213ced...
         ];
 
         let actual =
@@ -309,9 +503,9 @@ mod tests {
             .collect();
 
         let expected = vec![
-            PEER_IDS_32BYTE_LITERAL_CASE[4].as_bytes(), // b2c3d4e5... (Placeholder)
-            PEER_IDS_32BYTE_LITERAL_CASE[2].as_bytes(), // e42bbf85... (Placeholder)
-            PEER_IDS_32BYTE_LITERAL_CASE[1].as_bytes(), // 3b213ced... (Placeholder)
+            PEER_IDS_32BYTE_LITERAL_CASE[4].as_bytes(), // b2c3d4e5...
+            PEER_IDS_32BYTE_LITERAL_CASE[1].as_bytes(), // 3b213ced...
+            PEER_IDS_32BYTE_LITERAL_CASE[2].as_bytes(), // e42bbf85...
         ];
 
         let actual = xor_distance_selection(
@@ -326,4 +520,125 @@ mod tests {
         .unwrap();
         assert_eq!(actual, expected, "Selection mismatch");
     }
+
+    #[test]
+    fn test_batch_matches_individual_selection() {
+        let name = "test";
+        let seed = b"test-seed";
+        let n = 3;
+        let peerset = get_peerset_from_literals(&[0, 1, 2, 3, 4]);
+        let seqs = [1u64, 2];
+
+        let prefix = crate::hash::HashPrefix::new(name, seed, Sha256::new);
+        let batch_actual = xor_distance_selection_batch(&prefix, &seqs, n, &peerset, None);
+
+        for (i, &seq) in seqs.iter().enumerate() {
+            let individual =
+                xor_distance_selection(name, seed, seq, n, &peerset, Sha256::new, None).unwrap();
+            assert_eq!(batch_actual[i], individual, "Batch mismatch for seq {seq}");
+        }
+    }
+
+    #[test]
+    fn test_batch_with_no_ids_returns_empty_per_seq() {
+        let name = "test";
+        let seed = b"test-seed";
+        let peerset: Vec<String> = Vec::new();
+        let seqs = [1u64, 2];
+
+        let prefix = crate::hash::HashPrefix::new(name, seed, Sha256::new);
+        let batch_actual = xor_distance_selection_batch(&prefix, &seqs, 3, &peerset, None);
+
+        assert_eq!(
+            batch_actual,
+            vec![Vec::<Vec<u8>>::new(), Vec::<Vec<u8>>::new()]
+        );
+    }
+
+    #[test]
+    fn test_spread_with_32byte_literal_strings_spans_multiple_buckets() {
+        let name = "testgroup-1";
+        let seed = b"test-seed";
+        let seq = 10;
+        let n = 4;
+        let peerset: Vec<String> = PEER_IDS_32BYTE_LITERAL_CASE
+            .iter()
+            .map(|&s| s.to_string())
+            .collect();
+
+        let expected = vec![
+            PEER_IDS_32BYTE_LITERAL_CASE[4].as_bytes(), // b2c3d4e5...
+            PEER_IDS_32BYTE_LITERAL_CASE[1].as_bytes(), // 3b213ced...
+            PEER_IDS_32BYTE_LITERAL_CASE[3].as_bytes(), // a7a0243e...
+            PEER_IDS_32BYTE_LITERAL_CASE[0].as_bytes(), // 698750a0...
+        ];
+
+        let actual =
+            xor_distance_selection_spread(name, seed, seq, n, &peerset, Sha256::new).unwrap();
+        assert_eq!(actual, expected, "Spread selection mismatch");
+    }
+
+    #[test]
+    fn test_spread_with_n_covering_all_ids_returns_every_id_exactly_once() {
+        let name = "testgroup-1";
+        let seed = b"test-seed";
+        let seq = 10;
+        let peerset: Vec<String> = PEER_IDS_32BYTE_LITERAL_CASE
+            .iter()
+            .map(|&s| s.to_string())
+            .collect();
+
+        let actual =
+            xor_distance_selection_spread(name, seed, seq, peerset.len(), &peerset, Sha256::new)
+                .unwrap();
+
+        let mut sorted_actual = actual.clone();
+        sorted_actual.sort();
+        let mut sorted_expected: Vec<Vec<u8>> =
+            peerset.iter().map(|s| s.as_bytes().to_vec()).collect();
+        sorted_expected.sort();
+        assert_eq!(
+            sorted_actual, sorted_expected,
+            "Spread must cover every id exactly once"
+        );
+    }
+
+    #[test]
+    fn test_spread_exact_match_and_farthest_id_land_in_distinct_buckets() {
+        let name = "test";
+        let seed = b"test-seed";
+        let seq = 1;
+        let hash_bytes = crate::hash::compute_hash(name, seed, seq, Sha256::new);
+
+        // An exact match (distance 0) and an id that flips the hash's top bit (the true
+        // farthest distance) used to collide in the same "bucket 0" under the old formula.
+        let mut farthest = hash_bytes.clone();
+        farthest[0] ^= 0x80;
+        let mid = b"peer1".to_vec();
+
+        let ids = vec![farthest.clone(), hash_bytes.clone(), mid.clone()];
+
+        // Before the fix, the exact match and the farthest id shared bucket 0, so that bucket
+        // only contributed one pick per round; the mid-distance id's own (higher-priority)
+        // bucket would come up in the rotation before the exact match got its own turn,
+        // yielding `[mid, exact]` for the first two picks. With the exact match split into its
+        // own top bucket, it's the clear first pick.
+        let actual = xor_distance_selection_spread(name, seed, seq, 2, &ids, Sha256::new).unwrap();
+        assert_eq!(
+            actual,
+            vec![hash_bytes, mid],
+            "exact match must be the top pick, ahead of the mid-distance id"
+        );
+    }
+
+    #[test]
+    fn test_spread_no_nodes() {
+        let name = "test";
+        let seed = b"test-seed";
+        let seq = 1;
+        let peerset: Vec<String> = Vec::new();
+
+        let actual = xor_distance_selection_spread(name, seed, seq, 3, &peerset, Sha256::new);
+        assert_eq!(actual, None, "Spread selection mismatch for no nodes");
+    }
 }